@@ -0,0 +1,133 @@
+//! An `async` variant of the driver, enabled by the optional `async` feature. Mirrors
+//! [`crate::max6675`] over `embedded_hal_async`, sharing its frame-decoding logic.
+
+use embedded_hal_async::delay::DelayNs;
+use embedded_hal_async::spi::SpiDevice;
+
+use crate::max6675::{decode_frame, Error, Reading, DEFAULT_CONVERSION_INTERVAL_MILLIS};
+use crate::temperature::Temperature;
+
+pub struct Max6675<SPI, DELAY> {
+    spi: SPI,
+    delay: DELAY,
+    conversion_interval_millis: u32,
+}
+
+impl<SPI, DELAY, E> Max6675<SPI, DELAY>
+where
+    SPI: SpiDevice<u8, Error = E>,
+    DELAY: DelayNs,
+{
+    pub fn new(spi: SPI, delay: DELAY) -> Self {
+        Max6675 {
+            spi,
+            delay,
+            conversion_interval_millis: DEFAULT_CONVERSION_INTERVAL_MILLIS,
+        }
+    }
+
+    /// Override the conversion interval awaited by [`read`](Self::read).
+    pub fn with_conversion_interval_millis(mut self, interval_millis: u32) -> Self {
+        self.conversion_interval_millis = interval_millis;
+        self
+    }
+
+    /// Wait out the MAX6675's conversion interval, then return the current
+    /// temperature.
+    pub async fn read(&mut self) -> Result<Temperature, Error<E>> {
+        self.delay.delay_ms(self.conversion_interval_millis).await;
+
+        let reading = self.read_raw().await?;
+        if reading.is_open {
+            Err(Error::ThermocoupleDisconnected)
+        } else if reading.device_id != 0_u8 {
+            Err(Error::BusError)
+        } else {
+            Ok(Temperature::from_raw(reading.temp))
+        }
+    }
+
+    /// Read the full 16-bit frame from the MAX6675 over SPI, with the temperature and
+    /// diagnostic bits decoded but not interpreted.
+    pub async fn read_raw(&mut self) -> Result<Reading, Error<E>> {
+        let mut buffer = [0u8; 2];
+
+        self.spi.read(&mut buffer).await.map_err(Error::Spi)?;
+
+        Ok(decode_frame(buffer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_hal_async::spi::{ErrorType, Operation};
+    use futures::executor::block_on;
+
+    struct FakeSPI {
+        raw_data: [u8; 2],
+    }
+
+    impl ErrorType for FakeSPI {
+        type Error = core::convert::Infallible;
+    }
+
+    impl SpiDevice<u8> for FakeSPI {
+        async fn transaction(
+            &mut self,
+            operations: &mut [Operation<'_, u8>],
+        ) -> Result<(), Self::Error> {
+            for op in operations {
+                if let Operation::Read(buffer) = op {
+                    buffer.copy_from_slice(&self.raw_data);
+                }
+            }
+            Ok(())
+        }
+    }
+
+    struct FakeDelay;
+
+    impl DelayNs for FakeDelay {
+        async fn delay_ns(&mut self, _ns: u32) {}
+    }
+
+    #[test]
+    fn read_decodes_the_temperature() {
+        let mut max6675 = Max6675::new(
+            FakeSPI {
+                raw_data: [0b0000_0001, 0b0100_0000],
+            },
+            FakeDelay,
+        );
+
+        assert_eq!(block_on(max6675.read()).unwrap().as_celsius(), 10.0);
+    }
+
+    #[test]
+    fn read_reports_an_open_thermocouple() {
+        let mut max6675 = Max6675::new(
+            FakeSPI {
+                raw_data: [0b0000_0000, 0b0000_0100],
+            },
+            FakeDelay,
+        );
+
+        assert_eq!(
+            block_on(max6675.read()).unwrap_err(),
+            Error::ThermocoupleDisconnected
+        );
+    }
+
+    #[test]
+    fn read_reports_a_bad_device_id() {
+        let mut max6675 = Max6675::new(
+            FakeSPI {
+                raw_data: [0b0000_0000, 0b0000_0010],
+            },
+            FakeDelay,
+        );
+
+        assert_eq!(block_on(max6675.read()).unwrap_err(), Error::BusError);
+    }
+}
@@ -1,72 +1,190 @@
 //! A driver for MAX6675 using the embedded_hal SPI traits
 
-#![cfg_attr(not(test), no_std)]
+#![cfg_attr(not(any(test, feature = "linux")), no_std)]
+
+#[cfg(feature = "linux")]
+pub mod linux;
+
+#[cfg(feature = "async")]
+pub mod asynch;
+
+pub mod temperature {
+    /// Resolution of a single LSB of the MAX6675's 12-bit reading, in degrees Celsius.
+    pub const RESOLUTION_CELSIUS: f32 = 0.25;
+
+    /// A temperature reading from the MAX6675.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct Temperature {
+        raw: u16,
+    }
+
+    impl Temperature {
+        pub(crate) fn from_raw(raw: u16) -> Self {
+            Temperature { raw }
+        }
+
+        /// The raw 12-bit count, as reported by the MAX6675 (0.25 °C per LSB).
+        pub fn raw(&self) -> u16 {
+            self.raw
+        }
+
+        /// The resolution of one LSB of `raw()`, in degrees Celsius.
+        pub fn resolution(&self) -> f32 {
+            RESOLUTION_CELSIUS
+        }
+
+        /// The temperature in degrees Celsius.
+        pub fn as_celsius(&self) -> f32 {
+            self.raw as f32 * RESOLUTION_CELSIUS
+        }
+
+        /// The temperature in degrees Fahrenheit.
+        pub fn as_fahrenheit(&self) -> f32 {
+            self.as_celsius() * 9.0 / 5.0 + 32.0
+        }
+
+        /// The temperature in Kelvin.
+        pub fn as_kelvin(&self) -> f32 {
+            self.as_celsius() + 273.15
+        }
+    }
+}
 
 pub mod max6675 {
-    use embedded_hal::blocking::spi::Transfer;
-    use embedded_hal::digital::v2::OutputPin;
+    use crate::temperature::Temperature;
+    use embedded_hal::spi::SpiDevice;
+
+    /// Minimum time between conversions. See [`Max6675::read`].
+    pub const DEFAULT_CONVERSION_INTERVAL_MILLIS: u32 = 220;
 
-    pub struct Max6675<SPI, CS> {
-        pub spi: SPI,
-        pub cs: CS,
+    /// A monotonic millisecond time source for [`Max6675::read`].
+    pub trait Clock {
+        /// Milliseconds since an arbitrary, monotonically increasing epoch.
+        fn now_millis(&self) -> u32;
+    }
+
+    pub struct Max6675<SPI, CLOCK = ()> {
+        spi: SPI,
+        clock: CLOCK,
+        conversion_interval_millis: u32,
+        last_read_millis: Option<u32>,
     }
 
     #[derive(Debug, PartialEq)]
     /// MAX6675-specific errors
-    pub enum Error {
+    pub enum Error<E> {
+        /// The underlying `SpiDevice` (including chip-select) returned an error.
+        Spi(E),
         BusError,
         ThermocoupleDisconnected,
     }
 
-    /// Holds a "raw" reading - temperature as well as some diagnostic bits.
-    struct Reading {
-        temp: u16,
-        is_open: bool,
-        device_id: u8,
+    /// The full 16-bit frame read from the MAX6675, decoded into its component fields.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct Reading {
+        /// The raw 16-bit word, as shifted out of the MAX6675.
+        pub raw: u16,
+        /// The 12-bit temperature count (0.25 °C per LSB).
+        pub temp: u16,
+        /// Set when the thermocouple input is open (disconnected).
+        pub is_open: bool,
+        /// The device ID bit. Always 0 on a MAX6675.
+        pub device_id: u8,
     }
 
-    impl<SPI, CS, E> Max6675<SPI, CS>
+    impl<SPI, E> Max6675<SPI, ()>
     where
-        SPI: Transfer<u8, Error = E>,
-        CS: OutputPin,
+        SPI: SpiDevice<u8, Error = E>,
     {
-        pub fn new(spi: SPI, cs: CS) -> Self {
-            Max6675 { spi, cs }
-        }
-
-        /// Return the temperature in degrees celcius
-        pub fn get_temperature(&mut self) -> Result<f32, Error> {
-            let reading = self.read_spi();
-            match reading {
-                Ok(reading) => {
-                    if reading.is_open {
-                        Err(Error::ThermocoupleDisconnected)
-                    } else if reading.device_id != 0_u8 {
-                        Err(Error::BusError)
-                    } else {
-                        Ok(reading.temp as f32 * 0.25)
-                    }
-                }
-                Err(_) => Err(Error::BusError),
+        pub fn new(spi: SPI) -> Self {
+            Max6675 {
+                spi,
+                clock: (),
+                conversion_interval_millis: DEFAULT_CONVERSION_INTERVAL_MILLIS,
+                last_read_millis: None,
             }
         }
+    }
 
-        /// Read a raw value from the MAX6675 over SPI
-        fn read_spi(&mut self) -> Result<Reading, E> {
-            let _ = self.cs.set_low();
+    impl<SPI, CLOCK, E> Max6675<SPI, CLOCK>
+    where
+        SPI: SpiDevice<u8, Error = E>,
+    {
+        /// Return the current temperature.
+        pub fn get_temperature(&mut self) -> Result<Temperature, Error<E>> {
+            let reading = self.read_raw()?;
+            if reading.is_open {
+                Err(Error::ThermocoupleDisconnected)
+            } else if reading.device_id != 0_u8 {
+                Err(Error::BusError)
+            } else {
+                Ok(Temperature::from_raw(reading.temp))
+            }
+        }
 
+        /// Read the full 16-bit frame from the MAX6675 over SPI. `SpiDevice` owns
+        /// chip-select and bus locking, so this is just a single read transaction.
+        pub fn read_raw(&mut self) -> Result<Reading, Error<E>> {
             let mut buffer = [0u8; 2];
 
-            self.spi.transfer(&mut buffer)?;
+            self.spi.read(&mut buffer).map_err(Error::Spi)?;
 
-            let _ = self.cs.set_high();
-            let raw_reading: u16 = (buffer[0] as u16) << 8 | buffer[1] as u16;
+            Ok(decode_frame(buffer))
+        }
+    }
 
-            Ok(Reading {
-                temp: raw_reading >> 3,
-                is_open: ((raw_reading & 0b00000000_00000100) >> 2) == 1,
-                device_id: ((raw_reading & 0b00000000_00000010) >> 1) as u8,
-            })
+    impl<SPI, CLOCK, E> Max6675<SPI, CLOCK>
+    where
+        SPI: SpiDevice<u8, Error = E>,
+        CLOCK: Clock,
+    {
+        /// Build a driver that also tracks the MAX6675's conversion interval, for use
+        /// with [`read`](Self::read).
+        pub fn with_clock(spi: SPI, clock: CLOCK) -> Self {
+            Max6675 {
+                spi,
+                clock,
+                conversion_interval_millis: DEFAULT_CONVERSION_INTERVAL_MILLIS,
+                last_read_millis: None,
+            }
+        }
+
+        /// Override the minimum time [`read`](Self::read) waits between conversions.
+        pub fn with_conversion_interval_millis(mut self, interval_millis: u32) -> Self {
+            self.conversion_interval_millis = interval_millis;
+            self
+        }
+
+        /// Non-blocking read that respects the MAX6675's minimum conversion interval.
+        ///
+        /// Returns `Err(nb::Error::WouldBlock)` until at least
+        /// `conversion_interval_millis` (220 ms by default) has elapsed since the last
+        /// successful read.
+        pub fn read(&mut self) -> nb::Result<Temperature, Error<E>> {
+            let now = self.clock.now_millis();
+
+            if let Some(last_read_millis) = self.last_read_millis {
+                if now.wrapping_sub(last_read_millis) < self.conversion_interval_millis {
+                    return Err(nb::Error::WouldBlock);
+                }
+            }
+
+            let temperature = self.get_temperature().map_err(nb::Error::Other)?;
+            self.last_read_millis = Some(now);
+            Ok(temperature)
+        }
+    }
+
+    /// Decode a raw 16-bit MAX6675 frame into its component fields. Shared by the
+    /// blocking driver above and the `async` driver in [`crate::asynch`].
+    pub(crate) fn decode_frame(buffer: [u8; 2]) -> Reading {
+        let raw_reading: u16 = (buffer[0] as u16) << 8 | buffer[1] as u16;
+
+        Reading {
+            raw: raw_reading,
+            temp: raw_reading >> 3,
+            is_open: ((raw_reading & 0b00000000_00000100) >> 2) == 1,
+            device_id: ((raw_reading & 0b00000000_00000010) >> 1) as u8,
         }
     }
 
@@ -74,82 +192,144 @@ pub mod max6675 {
     mod tests {
         use super::*;
 
+        use embedded_hal::spi::{ErrorType, Operation};
+        use std::cell::Cell;
+        use std::rc::Rc;
+
         struct FakeSPI {
             raw_data: [u8; 2],
         }
 
-        struct FakeCS;
-
-        impl Transfer<u8> for FakeSPI {
-            type Error = ();
+        impl ErrorType for FakeSPI {
+            type Error = core::convert::Infallible;
+        }
 
-            fn transfer<'w>(&mut self, data: &'w mut [u8]) -> Result<&'w [u8], Self::Error> {
-                for (i, w) in data.iter_mut().enumerate() {
-                    *w = self.raw_data[i];
+        impl SpiDevice<u8> for FakeSPI {
+            fn transaction(
+                &mut self,
+                operations: &mut [Operation<'_, u8>],
+            ) -> Result<(), Self::Error> {
+                for op in operations {
+                    if let Operation::Read(buffer) = op {
+                        buffer.copy_from_slice(&self.raw_data);
+                    }
                 }
-                Ok(data)
+                Ok(())
             }
         }
 
-        impl OutputPin for FakeCS {
-            type Error = ();
-            fn set_low(&mut self) -> Result<(), <Self as OutputPin>::Error> {
-                Ok(())
+        /// A clock whose `now_millis()` is set by the test, so conversion-interval
+        /// gating can be exercised without actually sleeping. Cloning shares the
+        /// same counter, so the test can advance it after handing a clone to the
+        /// driver.
+        #[derive(Clone)]
+        struct FakeClock {
+            millis: Rc<Cell<u32>>,
+        }
+
+        impl FakeClock {
+            fn new(millis: u32) -> Self {
+                FakeClock {
+                    millis: Rc::new(Cell::new(millis)),
+                }
             }
-            fn set_high(&mut self) -> Result<(), <Self as OutputPin>::Error> {
-                Ok(())
+
+            fn advance(&self, millis: u32) {
+                self.millis.set(self.millis.get() + millis);
+            }
+        }
+
+        impl Clock for FakeClock {
+            fn now_millis(&self) -> u32 {
+                self.millis.get()
             }
         }
 
         #[test]
         fn parse_temp() {
             assert_eq!(
-                Max6675::new(
-                    FakeSPI {
-                        raw_data: [0b0111_1111, 0b1111_1000]
-                    },
-                    FakeCS
-                )
+                Max6675::new(FakeSPI {
+                    raw_data: [0b0111_1111, 0b1111_1000]
+                })
                 .get_temperature()
-                .unwrap(),
+                .unwrap()
+                .as_celsius(),
                 1023.75
             );
 
             assert_eq!(
-                Max6675::new(
-                    FakeSPI {
-                        raw_data: [0b0000_0000, 0b0000_0000]
-                    },
-                    FakeCS
-                )
+                Max6675::new(FakeSPI {
+                    raw_data: [0b0000_0000, 0b0000_0000]
+                })
                 .get_temperature()
-                .unwrap(),
+                .unwrap()
+                .as_celsius(),
                 0.0
             );
 
             assert_eq!(
-                Max6675::new(
-                    FakeSPI {
-                        raw_data: [0b0000_0000, 0b0000_0100]
-                    },
-                    FakeCS
-                )
+                Max6675::new(FakeSPI {
+                    raw_data: [0b0000_0000, 0b0000_0100]
+                })
                 .get_temperature()
                 .unwrap_err(),
                 Error::ThermocoupleDisconnected
             );
 
             assert_eq!(
-                Max6675::new(
-                    FakeSPI {
-                        raw_data: [0b0000_0000, 0b0000_0010]
-                    },
-                    FakeCS
-                )
+                Max6675::new(FakeSPI {
+                    raw_data: [0b0000_0000, 0b0000_0010]
+                })
                 .get_temperature()
                 .unwrap_err(),
                 Error::BusError
             );
         }
+
+        #[test]
+        fn temperature_unit_conversions() {
+            let temp = Max6675::new(FakeSPI {
+                raw_data: [0b0000_0001, 0b0100_0000],
+            })
+            .get_temperature()
+            .unwrap();
+
+            assert_eq!(temp.as_celsius(), 10.0);
+            assert_eq!(temp.as_fahrenheit(), 50.0);
+            assert_eq!(temp.as_kelvin(), 283.15);
+            assert_eq!(temp.raw(), 40);
+            assert_eq!(temp.resolution(), 0.25);
+        }
+
+        #[test]
+        fn read_blocks_until_conversion_interval_elapses() {
+            let clock = FakeClock::new(0);
+            let mut max6675 = Max6675::with_clock(
+                FakeSPI {
+                    raw_data: [0b0000_0000, 0b0000_0000],
+                },
+                clock.clone(),
+            );
+
+            assert_eq!(max6675.read().unwrap().as_celsius(), 0.0);
+            assert_eq!(max6675.read(), Err(nb::Error::WouldBlock));
+
+            clock.advance(DEFAULT_CONVERSION_INTERVAL_MILLIS);
+            assert_eq!(max6675.read().unwrap().as_celsius(), 0.0);
+        }
+
+        #[test]
+        fn read_raw_exposes_the_full_frame_and_diagnostic_bits() {
+            let reading = Max6675::new(FakeSPI {
+                raw_data: [0b0000_0000, 0b0000_0110],
+            })
+            .read_raw()
+            .unwrap();
+
+            assert_eq!(reading.raw, 0b0000_0000_0000_0110);
+            assert_eq!(reading.temp, 0);
+            assert!(reading.is_open);
+            assert_eq!(reading.device_id, 1);
+        }
     }
 }
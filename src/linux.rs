@@ -0,0 +1,119 @@
+//! A `linux` feature providing a constructor over `/dev/spidevX.Y`, for Raspberry
+//! Pi-class boards. The core `no_std` driver in [`crate::max6675`] is untouched.
+
+use std::io;
+use std::io::{Read, Write};
+use std::time::{Duration, Instant};
+
+use embedded_hal::spi::{ErrorType, Operation, SpiDevice};
+use spidev::{SpiModeFlags, Spidev, SpidevOptions, SpidevTransfer};
+
+use crate::max6675::{Clock, Error, Max6675};
+
+/// The error type reported by a [`LinuxMax6675`].
+pub type LinuxError = Error<IoError>;
+
+/// A [`Max6675`] opened over a Linux `spidev` device node.
+pub type LinuxMax6675 = Max6675<LinuxSpiDevice, SystemClock>;
+
+/// Wraps a `std::io::Error` as an `embedded_hal::spi::Error`.
+#[derive(Debug)]
+pub struct IoError(io::Error);
+
+impl From<io::Error> for IoError {
+    fn from(err: io::Error) -> Self {
+        IoError(err)
+    }
+}
+
+impl std::fmt::Display for IoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl embedded_hal::spi::Error for IoError {
+    fn kind(&self) -> embedded_hal::spi::ErrorKind {
+        embedded_hal::spi::ErrorKind::Other
+    }
+}
+
+/// Wraps a Linux `spidev` handle as an `embedded_hal::spi::SpiDevice`. CS is asserted
+/// and released by the kernel spidev driver around each transfer.
+pub struct LinuxSpiDevice(Spidev);
+
+impl ErrorType for LinuxSpiDevice {
+    type Error = IoError;
+}
+
+impl SpiDevice<u8> for LinuxSpiDevice {
+    fn transaction(&mut self, operations: &mut [Operation<'_, u8>]) -> Result<(), Self::Error> {
+        for operation in operations {
+            match operation {
+                Operation::Read(buffer) => self.0.read_exact(buffer)?,
+                Operation::Write(buffer) => self.0.write_all(buffer)?,
+                Operation::Transfer(read, write) => {
+                    self.0
+                        .transfer(&mut SpidevTransfer::read_write(write, read))?;
+                }
+                Operation::TransferInPlace(buffer) => {
+                    let write = buffer.to_vec();
+                    self.0
+                        .transfer(&mut SpidevTransfer::read_write(&write, buffer))?;
+                }
+                Operation::DelayNs(nanos) => {
+                    std::thread::sleep(Duration::from_nanos(*nanos as u64))
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A [`Clock`] backed by `std::time::Instant`.
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    fn new() -> Self {
+        SystemClock {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Clock for SystemClock {
+    fn now_millis(&self) -> u32 {
+        self.start.elapsed().as_millis() as u32
+    }
+}
+
+impl LinuxMax6675 {
+    /// Open the MAX6675 on the given spidev path (e.g. `/dev/spidev0.0`), configured
+    /// the way the MAX6675 needs: SPI mode 1, MSB-first, ~1 MHz.
+    pub fn from_spidev_path(path: &str) -> io::Result<Self> {
+        let mut spi = Spidev::open(path)?;
+        spi.configure(
+            &SpidevOptions::new()
+                .bits_per_word(8)
+                .max_speed_hz(1_000_000)
+                .mode(SpiModeFlags::SPI_MODE_1)
+                .build(),
+        )?;
+
+        Ok(Max6675::with_clock(LinuxSpiDevice(spi), SystemClock::new()))
+    }
+
+    /// Blocking convenience wrapper around [`Max6675::read`], honoring the MAX6675's
+    /// conversion interval by sleeping 1 ms and retrying after each `WouldBlock`.
+    pub fn read_celsius(&mut self) -> Result<f32, LinuxError> {
+        loop {
+            match self.read() {
+                Ok(temperature) => return Ok(temperature.as_celsius()),
+                Err(nb::Error::WouldBlock) => std::thread::sleep(Duration::from_millis(1)),
+                Err(nb::Error::Other(err)) => return Err(err),
+            }
+        }
+    }
+}